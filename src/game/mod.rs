@@ -1,7 +1,8 @@
 use anyhow::*;
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use serenity::model::id::{ChannelId, UserId};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
@@ -11,9 +12,9 @@ mod quiz;
 pub mod team;
 
 use self::quiz::definition::QuizDefinition;
-use self::quiz::Quiz;
-use self::team::{sanitize_name, Team, TeamId, TeamsHandle};
-use crate::output::{OutputPipe, Recipient};
+use self::quiz::{Quiz, QuizSnapshot};
+use self::team::{sanitize_name, Gadget, Team, TeamId, TeamsHandle};
+use crate::output::{GameEvent, OutputPipe, Recipient};
 
 enum Phase {
     Startup,
@@ -21,11 +22,43 @@ enum Phase {
     Quiz(Quiz),
 }
 
+/// Serializable counterpart of [`Phase`], used to checkpoint and restore a
+/// [`Game`]. Carries no runtime-only state (no `OutputPipe`, no `TeamsHandle`).
+#[derive(Debug, Serialize, Deserialize)]
+enum PhaseSnapshot {
+    Startup,
+    Setup,
+    Quiz(QuizSnapshot),
+}
+
+/// Everything needed to recreate a [`Game`] exactly as it was, short of the
+/// runtime-only `OutputPipe` which the caller must re-attach on restore.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    main_channel: ChannelId,
+    teams: Vec<Team>,
+    paused: bool,
+    phase: PhaseSnapshot,
+    master: Option<UserId>,
+}
+
+/// What happened when the room master stopped being on any team: who used to
+/// be in charge, and who (if anyone) took over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MasterSuccession {
+    pub old_master: UserId,
+    pub new_master: Option<UserId>,
+}
+
 pub struct Game {
     current_phase: Phase,
     teams: TeamsHandle,
     output_pipe: Arc<RwLock<OutputPipe>>,
     paused: bool,
+    /// The player allowed to run control commands (`begin`, `skip`,
+    /// `adjust_score`, `reset_scores`, `pause`). `None` until someone joins a
+    /// team, at which point they are elected automatically.
+    master: Option<UserId>,
 }
 
 impl Game {
@@ -35,6 +68,7 @@ impl Game {
             output_pipe: Arc::new(RwLock::new(output_pipe)),
             paused: false,
             teams,
+            master: None,
         };
         game.set_current_phase(Phase::Setup);
         game
@@ -44,6 +78,118 @@ impl Game {
         self.current_phase = phase;
     }
 
+    pub fn snapshot(&self) -> GameSnapshot {
+        let phase = match &self.current_phase {
+            Phase::Startup => PhaseSnapshot::Startup,
+            Phase::Setup => PhaseSnapshot::Setup,
+            Phase::Quiz(quiz) => PhaseSnapshot::Quiz(quiz.snapshot()),
+        };
+        GameSnapshot {
+            main_channel: self.output_pipe.read().main_channel(),
+            teams: self.teams.read().clone(),
+            paused: self.paused,
+            phase,
+            master: self.master,
+        }
+    }
+
+    /// Rebuilds a `Game` from a snapshot, re-attaching a fresh `OutputPipe`
+    /// (which is runtime-only and is never part of a snapshot).
+    pub fn restore(snapshot: GameSnapshot, output_pipe: OutputPipe) -> Game {
+        let teams: TeamsHandle = Arc::new(RwLock::new(snapshot.teams));
+        let output_pipe = Arc::new(RwLock::new(output_pipe));
+        let current_phase = match snapshot.phase {
+            PhaseSnapshot::Startup => Phase::Startup,
+            PhaseSnapshot::Setup => Phase::Setup,
+            PhaseSnapshot::Quiz(quiz_snapshot) => {
+                Phase::Quiz(Quiz::restore(quiz_snapshot, teams.clone(), output_pipe.clone()))
+            }
+        };
+        Game {
+            current_phase,
+            teams,
+            output_pipe,
+            paused: snapshot.paused,
+            master: snapshot.master,
+        }
+    }
+
+    /// Errors out unless `caller` is the current room master.
+    fn require_master(&self, caller: UserId) -> Result<()> {
+        match self.master {
+            Some(master) if master == caller => Ok(()),
+            Some(_) => Err(anyhow!("Only the room master can do that")),
+            None => Err(anyhow!("There is no room master yet")),
+        }
+    }
+
+    pub fn master(&self) -> Option<UserId> {
+        self.master
+    }
+
+    /// Hands control over to `new_master`, who must already be on a team.
+    /// Only the current master can do this.
+    pub fn transfer_master(&mut self, caller: UserId, new_master: UserId) -> Result<()> {
+        self.require_master(caller)?;
+        self.get_player_team(new_master)
+            .context("That player is not on a team")?;
+        self.master = Some(new_master);
+        self.output_pipe.read().say(
+            &Recipient::AllTeams,
+            &format!("<@{}> handed the room master role to <@{}>.", caller, new_master),
+        );
+        Ok(())
+    }
+
+    /// If `departed` was the room master and is no longer on any team, elects
+    /// a successor: first another member of `former_teammates` who is still
+    /// on a team, otherwise any remaining player. Announces the change
+    /// through `OutputPipe` either way. Returns `None` if `departed` was not
+    /// the master, or is still on a team.
+    fn reassign_master_after_departure(
+        &mut self,
+        departed: UserId,
+        former_teammates: &HashSet<UserId>,
+    ) -> Option<MasterSuccession> {
+        if self.master != Some(departed) || self.get_player_team(departed).is_some() {
+            return None;
+        }
+
+        let new_master = former_teammates
+            .iter()
+            .copied()
+            .find(|player| *player != departed && self.get_player_team(*player).is_some())
+            .or_else(|| {
+                self.teams
+                    .read()
+                    .iter()
+                    .flat_map(|team| team.players.iter())
+                    .copied()
+                    .next()
+            });
+        self.master = new_master;
+
+        let output_pipe = self.output_pipe.read();
+        match new_master {
+            Some(new_master) => output_pipe.say(
+                &Recipient::AllTeams,
+                &format!(
+                    "The room master left; <@{}> is now in charge.",
+                    new_master
+                ),
+            ),
+            None => output_pipe.say(
+                &Recipient::AllTeams,
+                "The room master left and no players remain to take over.",
+            ),
+        }
+
+        Some(MasterSuccession {
+            old_master: departed,
+            new_master,
+        })
+    }
+
     pub fn tick(&mut self, dt: Duration) {
         if self.paused {
             return;
@@ -59,7 +205,8 @@ impl Game {
         };
     }
 
-    pub fn begin(&mut self, quiz_path: &Path) -> Result<()> {
+    pub fn begin(&mut self, caller: UserId, quiz_path: &Path) -> Result<()> {
+        self.require_master(caller)?;
         match &self.current_phase {
             Phase::Setup => {
                 let definition = QuizDefinition::open(quiz_path)?;
@@ -71,7 +218,28 @@ impl Game {
         }
     }
 
-    pub fn skip(&mut self) -> Result<()> {
+    pub fn add_time(&mut self, duration: Duration) -> Result<()> {
+        match &mut self.current_phase {
+            Phase::Quiz(quiz) => {
+                quiz.add_time(duration);
+                Ok(())
+            }
+            _ => Err(anyhow!("There is no quiz in progress")),
+        }
+    }
+
+    pub fn set_time_remaining(&mut self, duration: Duration) -> Result<()> {
+        match &mut self.current_phase {
+            Phase::Quiz(quiz) => {
+                quiz.set_time_remaining(duration);
+                Ok(())
+            }
+            _ => Err(anyhow!("There is no quiz in progress")),
+        }
+    }
+
+    pub fn skip(&mut self, caller: UserId) -> Result<()> {
+        self.require_master(caller)?;
         match &mut self.current_phase {
             Phase::Quiz(q) => {
                 q.skip_phase();
@@ -88,14 +256,85 @@ impl Game {
 
         match &mut self.current_phase {
             Phase::Quiz(quiz) => {
-                quiz.guess(&team_id, guess)?;
-                Ok(())
+                let output_pipe = self.output_pipe.clone();
+                match quiz.guess(&team_id, guess) {
+                    Ok(()) => {
+                        output_pipe
+                            .read()
+                            .push_event(GameEvent::GuessAccepted { team: team_id });
+                        Ok(())
+                    }
+                    Err(error) => {
+                        output_pipe.read().push_event(GameEvent::GuessRejected {
+                            team: team_id,
+                            reason: error.to_string(),
+                        });
+                        Err(error)
+                    }
+                }
             }
             _ => Err(anyhow!("Cannot submit answers during setup phase")),
         }
     }
 
-    pub fn disband_team(&mut self, team_name: &str) -> Result<()> {
+    pub fn vote(&mut self, player: UserId, option_index: usize) -> Result<()> {
+        let team_id = self
+            .get_player_team(player)
+            .context("Player is not on a team")?;
+
+        match &mut self.current_phase {
+            Phase::Quiz(quiz) => {
+                quiz.vote(&team_id, option_index)?;
+                Ok(())
+            }
+            _ => Err(anyhow!("Cannot vote outside of a quiz")),
+        }
+    }
+
+    pub fn use_gadget(
+        &mut self,
+        player: UserId,
+        gadget: Gadget,
+        target_team_name: Option<&str>,
+    ) -> Result<()> {
+        if self.paused {
+            return Err(anyhow!("The game is paused"));
+        }
+        let team_id = self
+            .get_player_team(player)
+            .context("Player is not on a team")?;
+        let target = target_team_name
+            .map(sanitize_name)
+            .transpose()?
+            .map(TeamId::TeamName);
+
+        match &mut self.current_phase {
+            Phase::Quiz(quiz) => {
+                {
+                    let mut teams = self.teams.write();
+                    let team = teams
+                        .iter_mut()
+                        .find(|t| t.id == team_id)
+                        .context("Team not found")?;
+                    team.consume_gadget(gadget)?;
+                }
+                if let Err(error) = quiz.use_gadget(&team_id, gadget, target.as_ref()) {
+                    let mut teams = self.teams.write();
+                    if let Some(team) = teams.iter_mut().find(|t| t.id == team_id) {
+                        team.grant_gadget(gadget);
+                    }
+                    return Err(error);
+                }
+                self.output_pipe
+                    .read()
+                    .push_event(GameEvent::GadgetUsed { team: team_id, gadget });
+                Ok(())
+            }
+            _ => Err(anyhow!("Cannot use a gadget outside of a quiz")),
+        }
+    }
+
+    pub fn disband_team(&mut self, team_name: &str) -> Result<Option<MasterSuccession>> {
         let team_name = sanitize_name(team_name)?;
         let team_id = TeamId::TeamName(team_name);
         let mut teams = self.teams.write();
@@ -103,10 +342,23 @@ impl Game {
             .iter()
             .position(|t| t.id == team_id)
             .context("Team not found")?;
-        teams.swap_remove(index);
-        Ok(())
+        let departing_team = teams.swap_remove(index);
+        drop(teams);
+        self.output_pipe
+            .read()
+            .push_event(GameEvent::TeamLeft { team: team_id });
+
+        let succession = self
+            .master
+            .filter(|master| departing_team.players.contains(master))
+            .and_then(|master| {
+                self.reassign_master_after_departure(master, &departing_team.players)
+            });
+        Ok(succession)
     }
 
+    /// A player who joins a team always ends up on one, so this can never
+    /// trigger a master succession (unlike [`Game::disband_team`]).
     pub fn join_team(&mut self, player: UserId, team_name: &str) -> Result<()> {
         let is_setup_phase = match &self.current_phase {
             Phase::Setup => true,
@@ -139,17 +391,32 @@ impl Game {
 
         // Remove empty teams
         teams.retain(|t| !t.players.is_empty());
+        drop(teams);
+
+        self.output_pipe
+            .read()
+            .push_event(GameEvent::TeamJoined { team: team_id });
+
+        if self.master.is_none() {
+            self.master = Some(player);
+            self.output_pipe.read().say(
+                &Recipient::AllTeams,
+                &format!("<@{}> is now the room master.", player),
+            );
+        }
 
         Ok(())
     }
 
-    pub fn adjust_score(&mut self, team_id: TeamId, delta: i32) -> Result<()> {
+    pub fn adjust_score(&mut self, caller: UserId, team_id: TeamId, delta: i32) -> Result<()> {
+        self.require_master(caller)?;
         let mut teams = self.teams.write();
         let team = teams
             .iter_mut()
             .find(|t| t.id == team_id)
             .context("Team not found")?;
         team.update_score(delta);
+        let score = team.score;
         let output_pipe = self.output_pipe.read();
         output_pipe.say(
             &Recipient::AllTeams,
@@ -159,6 +426,10 @@ impl Game {
                 team.score
             ),
         );
+        output_pipe.push_event(GameEvent::ScoreUpdated {
+            team: team_id,
+            score,
+        });
         Ok(())
     }
 
@@ -168,7 +439,8 @@ impl Game {
         output_pipe.say(&Recipient::AllTeams, "Teams were reset");
     }
 
-    pub fn reset_scores(&mut self) {
+    pub fn reset_scores(&mut self, caller: UserId) -> Result<()> {
+        self.require_master(caller)?;
         {
             let mut teams = self.teams.write();
             for team in teams.iter_mut() {
@@ -177,9 +449,11 @@ impl Game {
         }
         let output_pipe = self.output_pipe.read();
         output_pipe.say(&Recipient::AllTeams, "Scores were reset");
+        Ok(())
     }
 
-    pub fn pause(&mut self) {
+    pub fn pause(&mut self, caller: UserId) -> Result<()> {
+        self.require_master(caller)?;
         if !self.paused {
             self.paused = true;
             let output_pipe = self.output_pipe.read();
@@ -188,6 +462,7 @@ impl Game {
                 "The game is now paused, use `!unpause` to resume.",
             );
         }
+        Ok(())
     }
 
     pub fn unpause(&mut self) {
@@ -215,3 +490,84 @@ impl Game {
         self.teams.read().clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::DiscordOutput;
+    use serenity::model::id::GuildId;
+
+    fn output_pipe() -> OutputPipe {
+        OutputPipe::new(GuildId::new(1), ChannelId::new(1), &Arc::new(DiscordOutput {}))
+    }
+
+    #[test]
+    fn game_snapshot_round_trips_through_json() {
+        let mut game = Game::new(output_pipe(), Arc::new(RwLock::new(Vec::new())));
+        game.join_team(UserId::new(1), "red").unwrap();
+        game.adjust_score(UserId::new(1), TeamId::TeamName("red".into()), 5)
+            .unwrap();
+
+        let snapshot = game.snapshot();
+        let json = serde_json::to_string(&snapshot).expect("snapshot should serialize");
+        let restored_snapshot: GameSnapshot =
+            serde_json::from_str(&json).expect("snapshot should deserialize");
+        let restored = Game::restore(restored_snapshot, output_pipe());
+
+        assert_eq!(restored.master(), game.master());
+        assert_eq!(restored.get_teams().len(), game.get_teams().len());
+        assert_eq!(
+            restored.get_teams()[0].score,
+            game.get_teams()[0].score
+        );
+    }
+
+    #[test]
+    fn master_succession_picks_a_remaining_player_when_the_masters_team_is_disbanded() {
+        let mut game = Game::new(output_pipe(), Arc::new(RwLock::new(Vec::new())));
+        let master = UserId::new(1);
+        let successor = UserId::new(2);
+
+        game.join_team(master, "red").unwrap();
+        game.join_team(successor, "blue").unwrap();
+        assert_eq!(game.master(), Some(master));
+
+        let succession = game.disband_team("red").unwrap();
+
+        assert_eq!(
+            succession,
+            Some(MasterSuccession {
+                old_master: master,
+                new_master: Some(successor),
+            })
+        );
+        assert_eq!(game.master(), Some(successor));
+    }
+
+    #[test]
+    fn master_succession_skips_former_teammates_when_their_whole_team_is_disbanded() {
+        let mut game = Game::new(output_pipe(), Arc::new(RwLock::new(Vec::new())));
+        let master = UserId::new(1);
+        let teammate = UserId::new(2);
+        let successor = UserId::new(3);
+
+        game.join_team(master, "red").unwrap();
+        game.join_team(teammate, "red").unwrap();
+        game.join_team(successor, "blue").unwrap();
+        assert_eq!(game.master(), Some(master));
+
+        // Disbanding "red" removes both `master` and `teammate` from any
+        // team, so `teammate` must not be elected even though it was in
+        // `master`'s former team.
+        let succession = game.disband_team("red").unwrap();
+
+        assert_eq!(
+            succession,
+            Some(MasterSuccession {
+                old_master: master,
+                new_master: Some(successor),
+            })
+        );
+        assert_eq!(game.master(), Some(successor));
+    }
+}
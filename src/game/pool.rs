@@ -4,19 +4,55 @@ use serenity::client::Context as SerenityContext;
 use serenity::model::id::{ChannelId, GuildId};
 use serenity::prelude::Mutex;
 use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::game::Game;
-use crate::output::OutputPipe;
+use crate::game::{Game, GameSnapshot};
+use crate::output::{DiscordOutput, OutputPipe, SpectatorBroadcaster};
 use crate::DiscordOutputManager;
 
+struct Checkpoint {
+    path: PathBuf,
+    interval: Duration,
+    since_last_save: Duration,
+}
+
 #[derive(Default)]
 pub struct Pool {
     games: RwLock<HashMap<GuildId, Arc<Mutex<Game>>>>,
+    checkpoint: RwLock<Option<Checkpoint>>,
+    /// Attached to every `OutputPipe` built after `enable_spectators` is
+    /// called, so spectator overlays receive events for games created (or
+    /// restored) from that point on.
+    spectators: RwLock<Option<SpectatorBroadcaster>>,
 }
 
 impl Pool {
+    /// Starts the spectator WebSocket server on `addr` and attaches it to
+    /// every `OutputPipe` built from now on. Call once at startup, before
+    /// `get_game`/`load_from`.
+    pub fn enable_spectators(&self, addr: &str) -> std::io::Result<()> {
+        let broadcaster = SpectatorBroadcaster::new();
+        broadcaster.listen(addr)?;
+        *self.spectators.write() = Some(broadcaster);
+        Ok(())
+    }
+
+    fn build_output_pipe(
+        &self,
+        guild: GuildId,
+        channel: ChannelId,
+        discord_output: &Arc<DiscordOutput>,
+    ) -> OutputPipe {
+        let pipe = OutputPipe::new(guild, channel, discord_output);
+        match self.spectators.read().clone() {
+            Some(broadcaster) => pipe.with_spectators(broadcaster),
+            None => pipe,
+        }
+    }
+
     pub fn get_game(&self, ctx: &SerenityContext, channel: ChannelId) -> Result<Arc<Mutex<Game>>> {
         let guild = ctx
             .cache
@@ -38,7 +74,7 @@ impl Pool {
                 .cloned()
                 .expect("Expected DiscordOutput in ShareMap.");
 
-            let dispatcher = OutputPipe::new(guild, channel, &discord_output);
+            let dispatcher = self.build_output_pipe(guild, channel, &discord_output);
             let mut map = self.games.write();
             map.insert(guild, Arc::new(Mutex::new(Game::new(dispatcher))));
         }
@@ -47,10 +83,76 @@ impl Pool {
     }
 
     pub fn tick(&self, dt: Duration) {
-        let map = self.games.read();
-        for (_channel, game) in map.iter() {
-            let mut game = game.lock();
-            game.tick(dt);
+        {
+            let map = self.games.read();
+            for (_channel, game) in map.iter() {
+                let mut game = game.lock();
+                game.tick(dt);
+            }
+        }
+        self.checkpoint_tick(dt);
+    }
+
+    /// Starts periodically writing a snapshot of every game to `path`, every
+    /// `interval`. Call once at startup, after `load_from` if resuming.
+    pub fn enable_checkpoints(&self, path: PathBuf, interval: Duration) {
+        *self.checkpoint.write() = Some(Checkpoint {
+            path,
+            interval,
+            since_last_save: Duration::default(),
+        });
+    }
+
+    fn checkpoint_tick(&self, dt: Duration) {
+        let path = {
+            let mut checkpoint = self.checkpoint.write();
+            match checkpoint.as_mut() {
+                Some(checkpoint) => {
+                    checkpoint.since_last_save += dt;
+                    if checkpoint.since_last_save < checkpoint.interval {
+                        return;
+                    }
+                    checkpoint.since_last_save = Duration::default();
+                    checkpoint.path.clone()
+                }
+                None => return,
+            }
+        };
+        if let Err(error) = self.save_to(&path) {
+            eprintln!("Failed to checkpoint game state to {:?}: {}", path, error);
+        }
+    }
+
+    /// Writes a snapshot of every tracked game, keyed by guild, as JSON.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let snapshots: HashMap<GuildId, GameSnapshot> = {
+            let map = self.games.read();
+            map.iter()
+                .map(|(guild, game)| (*guild, game.lock().snapshot()))
+                .collect()
+        };
+        let file = fs::File::create(path)
+            .with_context(|| format!("Could not create {:?}", path))?;
+        serde_json::to_writer_pretty(file, &snapshots)?;
+        Ok(())
+    }
+
+    /// Restores every game found in `path`, re-creating a fresh `OutputPipe`
+    /// for each one from `discord_output`. Call once at startup.
+    pub fn load_from(&self, path: &Path, discord_output: &Arc<DiscordOutput>) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let file =
+            fs::File::open(path).with_context(|| format!("Could not open {:?}", path))?;
+        let snapshots: HashMap<GuildId, GameSnapshot> = serde_json::from_reader(file)?;
+
+        let mut map = self.games.write();
+        for (guild, snapshot) in snapshots {
+            let output_pipe = self.build_output_pipe(guild, snapshot.main_channel, discord_output);
+            let game = Game::restore(snapshot, output_pipe);
+            map.insert(guild, Arc::new(Mutex::new(game)));
         }
+        Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,383 @@
+use anyhow::*;
+use parking_lot::RwLock;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod definition;
+mod phase;
+pub mod settings;
+
+use self::definition::{Question, QuizDefinition};
+use self::phase::*;
+use self::settings::Settings;
+use crate::game::team::{Gadget, TeamId, TeamsHandle};
+use crate::output::{GameEvent, OutputPipe, Payload, Recipient};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum QuizStep {
+    Startup(StartupStep),
+    Cooldown(CooldownStep),
+    Vote(VoteStep),
+    Question(QuestionStep),
+    Results(ResultsStep),
+    Wager(WagerStep),
+}
+
+/// Everything a `Quiz` needs to resume exactly where it left off, minus the
+/// runtime-only handles (`teams`, `output_pipe`) which the caller re-attaches
+/// on restore.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuizSnapshot {
+    settings: Settings,
+    remaining_questions: Vec<Question>,
+    current_step: QuizStep,
+    over: bool,
+}
+
+fn random_gadget() -> Gadget {
+    const GADGETS: [Gadget; 4] = [
+        Gadget::DoubleOrNothing,
+        Gadget::Steal,
+        Gadget::Freeze,
+        Gadget::FiftyFifty,
+    ];
+    *GADGETS.choose(&mut rand::thread_rng()).expect("GADGETS is not empty")
+}
+
+pub struct Quiz {
+    settings: Settings,
+    teams: TeamsHandle,
+    output_pipe: Arc<RwLock<OutputPipe>>,
+    remaining_questions: Vec<Question>,
+    current_step: QuizStep,
+    over: bool,
+    /// Time warning thresholds (from `settings.time_warning_thresholds`)
+    /// already announced for the current step.
+    warned_thresholds: HashSet<Duration>,
+}
+
+impl Quiz {
+    pub fn new(
+        definition: QuizDefinition,
+        teams: TeamsHandle,
+        output_pipe: Arc<RwLock<OutputPipe>>,
+    ) -> Quiz {
+        let settings: Settings = Default::default();
+        Quiz {
+            remaining_questions: definition.get_questions().clone(),
+            current_step: QuizStep::Startup(StartupStep::new(settings.cooldown_duration)),
+            settings,
+            teams,
+            output_pipe,
+            over: false,
+            warned_thresholds: HashSet::new(),
+        }
+    }
+
+    pub fn snapshot(&self) -> QuizSnapshot {
+        QuizSnapshot {
+            settings: self.settings.clone(),
+            remaining_questions: self.remaining_questions.clone(),
+            current_step: self.current_step.clone(),
+            over: self.over,
+        }
+    }
+
+    pub fn restore(
+        snapshot: QuizSnapshot,
+        teams: TeamsHandle,
+        output_pipe: Arc<RwLock<OutputPipe>>,
+    ) -> Quiz {
+        Quiz {
+            settings: snapshot.settings,
+            remaining_questions: snapshot.remaining_questions,
+            current_step: snapshot.current_step,
+            over: snapshot.over,
+            teams,
+            output_pipe,
+            warned_thresholds: HashSet::new(),
+        }
+    }
+
+    fn set_current_step(&mut self, step: QuizStep) {
+        self.current_step = step;
+        self.warned_thresholds.clear();
+    }
+
+    fn current_timer_mut(&mut self) -> Option<&mut CooldownStep> {
+        match &mut self.current_step {
+            QuizStep::Startup(step) => Some(step.timer_mut()),
+            QuizStep::Cooldown(step) => Some(step),
+            QuizStep::Vote(step) => Some(step.timer_mut()),
+            QuizStep::Question(step) => Some(step.timer_mut()),
+            QuizStep::Results(step) => Some(step.timer_mut()),
+            QuizStep::Wager(step) => Some(step.timer_mut()),
+        }
+    }
+
+    /// Adds time to the phase currently running, e.g. to cover for a
+    /// connectivity hiccup without skipping the whole question.
+    pub fn add_time(&mut self, duration: Duration) {
+        if let Some(timer) = self.current_timer_mut() {
+            timer.add_time(duration);
+        }
+    }
+
+    /// Sets how much time is left on the phase currently running.
+    pub fn set_time_remaining(&mut self, duration: Duration) {
+        if let Some(timer) = self.current_timer_mut() {
+            timer.set_time_remaining(duration);
+        }
+    }
+
+    fn announce_time_warnings(&mut self) {
+        let (remaining, time_to_wait) = match self.current_timer_mut() {
+            Some(timer) => (timer.remaining(), timer.time_to_wait()),
+            None => return,
+        };
+        let thresholds = self.settings.time_warning_thresholds.clone();
+        for threshold in thresholds {
+            // A threshold that isn't shorter than the phase's own duration
+            // would fire immediately on the first tick (e.g. the 5s startup
+            // phase hitting the default 10s threshold), which is never a
+            // meaningful warning.
+            if threshold >= time_to_wait {
+                continue;
+            }
+            if remaining <= threshold && self.warned_thresholds.insert(threshold) {
+                let output_pipe = self.output_pipe.read();
+                output_pipe.say(
+                    &Recipient::AllTeams,
+                    &format!("{} second(s) remaining!", threshold.as_secs()),
+                );
+            }
+        }
+    }
+
+    fn begin_vote(&mut self) {
+        let mut categories: Vec<String> = self
+            .remaining_questions
+            .iter()
+            .map(|q| q.category.clone())
+            .collect();
+        categories.sort();
+        categories.dedup();
+        if categories.is_empty() {
+            self.begin_question();
+            return;
+        }
+        self.set_current_step(QuizStep::Vote(VoteStep::new(
+            categories,
+            self.settings.vote_duration,
+        )));
+        self.announce_phase_changed("vote");
+    }
+
+    fn begin_question_with_category(&mut self, category: &str) {
+        let index = self
+            .remaining_questions
+            .iter()
+            .position(|q| q.category == category);
+        match index {
+            Some(index) => {
+                let question = self.remaining_questions.remove(index);
+                self.announce_question_shown(&question);
+                self.set_current_step(QuizStep::Question(QuestionStep::new(
+                    question,
+                    self.settings.question_duration,
+                )));
+                self.announce_phase_changed("question");
+            }
+            None => self.begin_question(),
+        }
+    }
+
+    fn begin_question(&mut self) {
+        if self.remaining_questions.is_empty() {
+            self.over = true;
+            return;
+        }
+        let question = self.remaining_questions.remove(0);
+        self.announce_question_shown(&question);
+        self.set_current_step(QuizStep::Question(QuestionStep::new(
+            question,
+            self.settings.question_duration,
+        )));
+        self.announce_phase_changed("question");
+    }
+
+    fn announce_phase_changed(&self, phase: &str) {
+        self.output_pipe.read().push_event(GameEvent::PhaseChanged {
+            phase: phase.to_string(),
+        });
+    }
+
+    fn announce_question_shown(&self, question: &Question) {
+        self.output_pipe.read().push_event(GameEvent::QuestionShown {
+            category: question.category.clone(),
+            points: question.points,
+        });
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        let teams_count = self.teams.read().len();
+        match &mut self.current_step {
+            QuizStep::Startup(step) => {
+                step.tick(dt);
+                if step.is_over() {
+                    self.begin_vote();
+                }
+            }
+            QuizStep::Cooldown(step) => {
+                step.tick(dt);
+                if step.is_over() {
+                    self.begin_question();
+                    let output_pipe = self.output_pipe.read();
+                    output_pipe.push(Payload::Text("Time for a question!".into()));
+                }
+            }
+            QuizStep::Vote(step) => {
+                step.tick(dt);
+                if step.is_over(teams_count) {
+                    let winner = step.winning_option_name();
+                    let output_pipe = self.output_pipe.read();
+                    output_pipe.say(
+                        &Recipient::AllTeams,
+                        &format!("The teams picked: {}", winner),
+                    );
+                    drop(output_pipe);
+                    self.begin_question_with_category(&winner);
+                }
+            }
+            QuizStep::Question(step) => {
+                step.tick(dt);
+                if step.is_over() {
+                    self.resolve_question_scores();
+                    self.set_current_step(QuizStep::Results(ResultsStep::new(
+                        self.settings.results_duration,
+                    )));
+                    self.announce_phase_changed("results");
+                }
+            }
+            QuizStep::Results(step) => {
+                step.tick(dt);
+                if step.is_over() {
+                    self.begin_vote();
+                }
+            }
+            QuizStep::Wager(step) => {
+                step.tick(dt);
+            }
+        };
+        self.announce_time_warnings();
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    pub fn skip_phase(&mut self) {
+        match &mut self.current_step {
+            QuizStep::Startup(_) => self.begin_vote(),
+            QuizStep::Cooldown(_) => self.begin_question(),
+            QuizStep::Vote(step) => {
+                let winner = step.winning_option_name();
+                self.begin_question_with_category(&winner);
+            }
+            QuizStep::Question(_) => {
+                self.resolve_question_scores();
+                self.set_current_step(QuizStep::Results(ResultsStep::new(
+                    self.settings.results_duration,
+                )));
+                self.announce_phase_changed("results");
+            }
+            QuizStep::Results(_) => self.begin_vote(),
+            QuizStep::Wager(_) => self.begin_question(),
+        }
+    }
+
+    fn resolve_question_scores(&mut self) {
+        let (deltas, fastest_correct_team) = match &self.current_step {
+            QuizStep::Question(step) => (step.resolve_scores(), step.fastest_correct_team()),
+            _ => return,
+        };
+
+        let mut teams = self.teams.write();
+        for delta in &deltas {
+            if let Some(team) = teams.iter_mut().find(|t| t.id == delta.team_id) {
+                team.update_score(delta.delta);
+            }
+        }
+        // Reward the fastest correct team with a random gadget for the rest
+        // of the quiz.
+        if let Some(team_id) = &fastest_correct_team {
+            if let Some(team) = teams.iter_mut().find(|t| t.id == *team_id) {
+                team.grant_gadget(random_gadget());
+            }
+        }
+        drop(teams);
+
+        let output_pipe = self.output_pipe.read();
+        for delta in &deltas {
+            output_pipe.say(
+                &Recipient::Team(delta.team_id.clone()),
+                &format!("Your team scored {} point(s) on this question.", delta.delta),
+            );
+        }
+        if let Some(team_id) = fastest_correct_team {
+            output_pipe.say(
+                &Recipient::Team(team_id.clone()),
+                &format!("Team {} earned a gadget for answering fastest!", team_id.name()),
+            );
+        }
+    }
+
+    pub fn guess(&mut self, team_id: &TeamId, guess: &str) -> Result<()> {
+        match &mut self.current_step {
+            QuizStep::Question(step) => step.guess(team_id, guess),
+            _ => Err(anyhow!("There is no question to answer right now")),
+        }
+    }
+
+    pub fn use_gadget(
+        &mut self,
+        team_id: &TeamId,
+        gadget: Gadget,
+        target: Option<&TeamId>,
+    ) -> Result<()> {
+        match &mut self.current_step {
+            QuizStep::Question(step) => {
+                step.apply_gadget(team_id, gadget, target)?;
+                let output_pipe = self.output_pipe.read();
+                output_pipe.say(
+                    &Recipient::AllTeams,
+                    &format!("Team {} used {:?}!", team_id.name(), gadget),
+                );
+                Ok(())
+            }
+            _ => Err(anyhow!("Gadgets can only be used during a question")),
+        }
+    }
+
+    pub fn vote(&mut self, team_id: &TeamId, option_index: usize) -> Result<()> {
+        match &mut self.current_step {
+            QuizStep::Vote(step) => {
+                step.register_vote(team_id, option_index)?;
+                let output_pipe = self.output_pipe.read();
+                output_pipe.say(
+                    &Recipient::AllTeams,
+                    &format!("Current tally:\n{}", step.format_tally()),
+                );
+                output_pipe.push_event(GameEvent::VoteTally {
+                    options: step.options().to_vec(),
+                    counts: step.counts(),
+                });
+                Ok(())
+            }
+            _ => Err(anyhow!("There is no vote in progress")),
+        }
+    }
+}
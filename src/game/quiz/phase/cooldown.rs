@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CooldownStep {
+    time_elapsed: Duration,
+    time_to_wait: Duration,
+}
+
+impl CooldownStep {
+    pub fn new(duration: Duration) -> Self {
+        CooldownStep {
+            time_elapsed: Duration::default(),
+            time_to_wait: duration,
+        }
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        self.time_elapsed += dt;
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.time_elapsed >= self.time_to_wait
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.time_to_wait.saturating_sub(self.time_elapsed)
+    }
+
+    /// The total duration this step was given to run, including any time
+    /// added or removed since it started.
+    pub fn time_to_wait(&self) -> Duration {
+        self.time_to_wait
+    }
+
+    pub fn add_time(&mut self, duration: Duration) {
+        self.time_to_wait += duration;
+    }
+
+    /// Sets how much time is left before the step expires, clamped so it
+    /// never drops below the time already elapsed (a host can't rewind a
+    /// phase that is already over).
+    pub fn set_time_remaining(&mut self, duration: Duration) {
+        self.time_to_wait = self.time_elapsed + duration;
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::game::quiz::definition::Question;
+use crate::game::team::{Gadget, TeamId};
+
+use super::CooldownStep;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionStep {
+    pub question: Question,
+    answers: HashMap<TeamId, (String, Duration)>,
+    frozen_teams: HashSet<TeamId>,
+    double_or_nothing_teams: HashSet<TeamId>,
+    steal_teams: HashSet<TeamId>,
+    fifty_fifty_options: HashMap<TeamId, Vec<String>>,
+    timer: CooldownStep,
+}
+
+/// Score delta earned (or lost) by a team once a question is resolved.
+pub struct ScoreDelta {
+    pub team_id: TeamId,
+    pub delta: i32,
+}
+
+impl QuestionStep {
+    pub fn new(question: Question, duration: Duration) -> Self {
+        QuestionStep {
+            question,
+            answers: HashMap::new(),
+            frozen_teams: HashSet::new(),
+            double_or_nothing_teams: HashSet::new(),
+            steal_teams: HashSet::new(),
+            fifty_fifty_options: HashMap::new(),
+            timer: CooldownStep::new(duration),
+        }
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        self.timer.tick(dt);
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.timer.is_over()
+    }
+
+    pub fn timer_mut(&mut self) -> &mut CooldownStep {
+        &mut self.timer
+    }
+
+    pub fn guess(&mut self, team_id: &TeamId, guess: &str) -> Result<()> {
+        if self.frozen_teams.remove(team_id) {
+            return Err(anyhow!("Your team is frozen and can not answer this question"));
+        }
+        self.answers
+            .insert(team_id.clone(), (guess.to_string(), self.timer.remaining()));
+        Ok(())
+    }
+
+    /// The options a team should be shown, after any `FiftyFifty` reduction.
+    pub fn options_for(&self, team_id: &TeamId) -> &[String] {
+        self.fifty_fifty_options
+            .get(team_id)
+            .map(Vec::as_slice)
+            .unwrap_or(&self.question.options)
+    }
+
+    pub fn apply_gadget(
+        &mut self,
+        team_id: &TeamId,
+        gadget: Gadget,
+        target: Option<&TeamId>,
+    ) -> Result<()> {
+        match gadget {
+            Gadget::DoubleOrNothing => {
+                self.double_or_nothing_teams.insert(team_id.clone());
+                Ok(())
+            }
+            Gadget::Steal => {
+                self.steal_teams.insert(team_id.clone());
+                Ok(())
+            }
+            Gadget::Freeze => {
+                let target = target.context("Freeze needs a target team")?;
+                self.frozen_teams.insert(target.clone());
+                Ok(())
+            }
+            Gadget::FiftyFifty => {
+                if self.question.options.len() < 2 {
+                    return Err(anyhow!("This question has no wrong options to remove"));
+                }
+                let wrong_options: Vec<String> = self
+                    .question
+                    .options
+                    .iter()
+                    .filter(|option| **option != self.question.answer)
+                    .cloned()
+                    .collect();
+                let keep = wrong_options.len() / 2;
+                let mut reduced: Vec<String> = wrong_options.into_iter().take(keep).collect();
+                reduced.push(self.question.answer.clone());
+                self.fifty_fifty_options.insert(team_id.clone(), reduced);
+                Ok(())
+            }
+        }
+    }
+
+    /// The team that answered correctly with the most time left, if any.
+    pub fn fastest_correct_team(&self) -> Option<TeamId> {
+        let is_correct = |guess: &str| guess.trim().eq_ignore_ascii_case(self.question.answer.trim());
+        self.answers
+            .iter()
+            .filter(|(_, (guess, _))| is_correct(guess))
+            .min_by_key(|(_, (_, remaining))| std::cmp::Reverse(*remaining))
+            .map(|(team_id, _)| team_id.clone())
+    }
+
+    /// Computes the score delta for every team that submitted a guess,
+    /// applying `DoubleOrNothing`/`Steal` modifiers. Called once the question
+    /// is resolved, before moving on to the results step.
+    pub fn resolve_scores(&self) -> Vec<ScoreDelta> {
+        let is_correct = |guess: &str| guess.trim().eq_ignore_ascii_case(self.question.answer.trim());
+
+        let fastest_correct_team = self
+            .answers
+            .iter()
+            .filter(|(_, (guess, _))| is_correct(guess))
+            .min_by_key(|(_, (_, remaining))| std::cmp::Reverse(*remaining))
+            .map(|(team_id, _)| team_id.clone());
+
+        let mut deltas: HashMap<TeamId, i32> = HashMap::new();
+        for (team_id, (guess, _)) in &self.answers {
+            let correct = is_correct(guess);
+            let mut delta = if correct { self.question.points } else { 0 };
+            if self.double_or_nothing_teams.contains(team_id) {
+                delta = if correct {
+                    self.question.points * 2
+                } else {
+                    -self.question.points
+                };
+            }
+            *deltas.entry(team_id.clone()).or_insert(0) += delta;
+        }
+
+        for stealer in &self.steal_teams {
+            let victim = match &fastest_correct_team {
+                Some(victim) if victim != stealer => victim,
+                _ => continue,
+            };
+            let stolen = deltas.get(victim).copied().unwrap_or(0);
+            *deltas.entry(victim.clone()).or_insert(0) -= stolen;
+            *deltas.entry(stealer.clone()).or_insert(0) += stolen;
+        }
+
+        deltas
+            .into_iter()
+            .map(|(team_id, delta)| ScoreDelta { team_id, delta })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question() -> Question {
+        Question {
+            text: "2+2?".into(),
+            answer: "4".into(),
+            category: "math".into(),
+            points: 10,
+            options: vec!["3".into(), "4".into()],
+        }
+    }
+
+    #[test]
+    fn resolve_scores_applies_double_or_nothing_and_steal() {
+        let mut step = QuestionStep::new(question(), Duration::from_secs(20));
+        let team_a = TeamId::TeamName("a".into());
+        let team_b = TeamId::TeamName("b".into());
+
+        step.guess(&team_a, "4").unwrap();
+        step.tick(Duration::from_secs(5));
+        step.guess(&team_b, "4").unwrap();
+
+        step.apply_gadget(&team_a, Gadget::DoubleOrNothing, None).unwrap();
+        step.apply_gadget(&team_b, Gadget::Steal, None).unwrap();
+
+        let deltas = step.resolve_scores();
+        let delta_for = |team_id: &TeamId| {
+            deltas
+                .iter()
+                .find(|delta| delta.team_id == *team_id)
+                .map(|delta| delta.delta)
+                .unwrap_or(0)
+        };
+
+        // team_a doubled its points for answering correctly, but team_b
+        // stole everything team_a earned since team_a was fastest.
+        assert_eq!(delta_for(&team_a), 0);
+        assert_eq!(delta_for(&team_b), 30);
+    }
+}
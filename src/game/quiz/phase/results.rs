@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::CooldownStep;
+
+/// Pause after a question has been scored, before the next vote. Holds only a
+/// timer; the correct answer and the scores are not announced by this step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultsStep {
+    timer: CooldownStep,
+}
+
+impl ResultsStep {
+    pub fn new(duration: Duration) -> Self {
+        ResultsStep {
+            timer: CooldownStep::new(duration),
+        }
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        self.timer.tick(dt);
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.timer.is_over()
+    }
+
+    pub fn timer_mut(&mut self) -> &mut CooldownStep {
+        &mut self.timer
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::CooldownStep;
+
+/// Brief announcement shown once at the very beginning of a quiz, before the
+/// first vote starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupStep {
+    timer: CooldownStep,
+}
+
+impl StartupStep {
+    pub fn new(duration: Duration) -> Self {
+        StartupStep {
+            timer: CooldownStep::new(duration),
+        }
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        self.timer.tick(dt);
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.timer.is_over()
+    }
+
+    pub fn timer_mut(&mut self) -> &mut CooldownStep {
+        &mut self.timer
+    }
+}
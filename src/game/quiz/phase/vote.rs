@@ -0,0 +1,127 @@
+use anyhow::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use crate::game::team::TeamId;
+
+use super::CooldownStep;
+
+/// Team vote on the category/difficulty of the next question.
+///
+/// Each team gets exactly one vote (later votes from the same team overwrite
+/// earlier ones). The step ends as soon as every team has voted, or when the
+/// embedded cooldown expires, whichever comes first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteStep {
+    options: Vec<String>,
+    tally: HashMap<TeamId, usize>,
+    timer: CooldownStep,
+}
+
+impl VoteStep {
+    pub fn new(options: Vec<String>, duration: Duration) -> Self {
+        VoteStep {
+            options,
+            tally: HashMap::new(),
+            timer: CooldownStep::new(duration),
+        }
+    }
+
+    /// Deterministic seed for tie-breaking, derived from the options on the
+    /// table. Using the options themselves (rather than system entropy)
+    /// means a tie is broken the same way across a snapshot/restore.
+    fn tiebreak_seed(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.options.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        self.timer.tick(dt);
+    }
+
+    pub fn is_over(&self, teams_count: usize) -> bool {
+        self.timer.is_over() || (teams_count > 0 && self.tally.len() >= teams_count)
+    }
+
+    pub fn timer_mut(&mut self) -> &mut super::CooldownStep {
+        &mut self.timer
+    }
+
+    pub fn register_vote(&mut self, team_id: &TeamId, option_index: usize) -> Result<()> {
+        if option_index >= self.options.len() {
+            return Err(anyhow!("There is no such option"));
+        }
+        self.tally.insert(team_id.clone(), option_index);
+        Ok(())
+    }
+
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// One entry per option, in the same order as `options`, with the number
+    /// of teams currently voting for it.
+    pub fn counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.options.len()];
+        for option_index in self.tally.values() {
+            counts[*option_index] += 1;
+        }
+        counts
+    }
+
+    pub fn format_tally(&self) -> String {
+        self.counts()
+            .into_iter()
+            .zip(self.options.iter())
+            .map(|(count, option)| format!("{}: {} vote(s)", option, count))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Picks the option with the most votes, breaking ties with an RNG seeded
+    /// by `tiebreak_seed`, so the same tie always yields the same winner.
+    pub fn winning_option(&mut self) -> usize {
+        let counts = self.counts();
+        let highest = counts.iter().copied().max().unwrap_or(0);
+        let winners: Vec<usize> = counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count == highest)
+            .map(|(index, _)| index)
+            .collect();
+        let mut rng = StdRng::seed_from_u64(self.tiebreak_seed());
+        winners[rng.gen_range(0..winners.len())]
+    }
+
+    pub fn winning_option_name(&mut self) -> String {
+        let index = self.winning_option();
+        self.options[index].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tied_winning_option_is_picked_deterministically() {
+        let options = vec!["history".to_string(), "science".to_string()];
+        let mut step_a = VoteStep::new(options.clone(), Duration::from_secs(15));
+        let mut step_b = VoteStep::new(options, Duration::from_secs(15));
+
+        step_a.register_vote(&TeamId::TeamName("a".into()), 0).unwrap();
+        step_a.register_vote(&TeamId::TeamName("b".into()), 1).unwrap();
+        step_b.register_vote(&TeamId::TeamName("a".into()), 0).unwrap();
+        step_b.register_vote(&TeamId::TeamName("b".into()), 1).unwrap();
+
+        // Same options, same tie: the tiebreak must pick the same winner
+        // every time, so it survives a snapshot/restore.
+        assert_eq!(step_a.winning_option(), step_b.winning_option());
+    }
+}
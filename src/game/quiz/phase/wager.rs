@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::CooldownStep;
+
+/// Wager phase before a question. Holds only a timer: it records no stakes
+/// and changes no scores.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WagerStep {
+    timer: CooldownStep,
+}
+
+impl WagerStep {
+    pub fn new(duration: Duration) -> Self {
+        WagerStep {
+            timer: CooldownStep::new(duration),
+        }
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        self.timer.tick(dt);
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.timer.is_over()
+    }
+
+    pub fn timer_mut(&mut self) -> &mut CooldownStep {
+        &mut self.timer
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    pub cooldown_duration: Duration,
+    pub vote_duration: Duration,
+    pub question_duration: Duration,
+    pub results_duration: Duration,
+    /// Thresholds (in descending order) at which a "time remaining" warning
+    /// is announced for the phase currently counting down.
+    pub time_warning_thresholds: Vec<Duration>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            cooldown_duration: Duration::from_secs(5),
+            vote_duration: Duration::from_secs(15),
+            question_duration: Duration::from_secs(20),
+            results_duration: Duration::from_secs(8),
+            time_warning_thresholds: vec![Duration::from_secs(10), Duration::from_secs(5)],
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use anyhow::*;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+pub type TeamsHandle = Arc<RwLock<Vec<Team>>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TeamId {
+    TeamName(String),
+}
+
+impl TeamId {
+    pub fn name(&self) -> &str {
+        match self {
+            TeamId::TeamName(name) => name,
+        }
+    }
+}
+
+/// A one-shot ability a team can spend during a question to tilt the odds in
+/// its favor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Gadget {
+    /// Doubles the team's gain on a correct answer, but costs them the same
+    /// amount on a wrong one.
+    DoubleOrNothing,
+    /// Redirects the points of the fastest correct opponent to this team.
+    Steal,
+    /// Blocks a target team's next guess.
+    Freeze,
+    /// Removes half of the wrong options before the team answers.
+    FiftyFifty,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Team {
+    pub id: TeamId,
+    pub players: HashSet<UserId>,
+    pub score: i32,
+    pub gadgets: Vec<Gadget>,
+}
+
+impl Team {
+    pub fn new(id: TeamId) -> Team {
+        Team {
+            id,
+            players: HashSet::new(),
+            score: 0,
+            gadgets: Vec::new(),
+        }
+    }
+
+    pub fn get_display_name(&self) -> &str {
+        match &self.id {
+            TeamId::TeamName(name) => name,
+        }
+    }
+
+    pub fn update_score(&mut self, delta: i32) {
+        self.score += delta;
+    }
+
+    pub fn grant_gadget(&mut self, gadget: Gadget) {
+        self.gadgets.push(gadget);
+    }
+
+    /// Removes one instance of `gadget` from the inventory, if the team has
+    /// one available.
+    pub fn consume_gadget(&mut self, gadget: Gadget) -> Result<()> {
+        let index = self
+            .gadgets
+            .iter()
+            .position(|g| *g == gadget)
+            .context("Team does not have this gadget")?;
+        self.gadgets.remove(index);
+        Ok(())
+    }
+}
+
+/// Discord channel/team names only allow a conservative charset so that they
+/// can safely be reused as channel names and map keys.
+pub fn sanitize_name(name: &str) -> Result<String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(anyhow!("Team name can not be empty"));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Team name can only contain letters, digits, '-' and '_'"
+        ));
+    }
+    Ok(name)
+}
@@ -0,0 +1,13 @@
+use serenity::prelude::TypeMapKey;
+use std::sync::Arc;
+
+pub mod game;
+pub mod output;
+
+use output::DiscordOutput;
+
+pub struct DiscordOutputManager;
+
+impl TypeMapKey for DiscordOutputManager {
+    type Value = Arc<DiscordOutput>;
+}
